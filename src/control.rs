@@ -0,0 +1,65 @@
+
+use std::io::{Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+
+use crate::path;
+
+/// Requests that a one-shot CLI invocation can send to a running `run` loop
+/// over the control socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlRequest {
+    Next,
+    Prev,
+}
+
+impl ControlRequest {
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            ControlRequest::Next => b"next",
+            ControlRequest::Prev => b"prev",
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            b"next" => Some(ControlRequest::Next),
+            b"prev" => Some(ControlRequest::Prev),
+            _ => None,
+        }
+    }
+}
+
+/// Binds the per-user control socket and spawns the dedicated thread that
+/// forwards requests from one-shot `next`/`prev` invocations to `tx`.
+pub fn spawn_listener(tx: std::sync::mpsc::Sender<ControlRequest>) -> Result<(), std::io::Error> {
+    let socket_path = path::get_socket_path();
+    if let Some(parent) = socket_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = UnixListener::bind(&socket_path)?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            let mut buf = [0u8; 16];
+            let Ok(n) = stream.read(&mut buf) else {
+                continue;
+            };
+            if let Some(request) = ControlRequest::parse(&buf[..n]) {
+                let _ = tx.send(request);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Connects to a running `run` loop's control socket and sends it `request`.
+pub fn send_request(request: ControlRequest) -> Result<(), std::io::Error> {
+    let mut stream = UnixStream::connect(path::get_socket_path())?;
+    stream.write_all(request.as_bytes())?;
+    Ok(())
+}
@@ -1,7 +1,13 @@
 
-use crate::PlatformProvider;
-use crate::PlatformInstaller;
+use tokio::io::AsyncWriteExt;
+
+use std::{path::PathBuf, sync::atomic::AtomicUsize};
+
+use parking_lot::Mutex;
+
 use crate::path::get_home_relative_path;
+use crate::PlatformInstaller;
+use crate::PlatformProvider;
 
 const USER_SERVICE_TEMPLATE: &str = r#"
 [Unit]
@@ -36,18 +42,237 @@ impl PlatformInstaller for GnuLinuxInstaller {
         command.arg("--user");
         command.arg("enable");
         command.arg("potd-wallpaper.service");
+        crate::sandbox::normalize_command(&mut command);
         command.output()?;
 
         let mut command = std::process::Command::new("systemctl");
         command.arg("--user");
         command.arg("restart");
         command.arg("potd-wallpaper.service");
+        crate::sandbox::normalize_command(&mut command);
         command.output()?;
         Ok(())
     }
+
+    fn uninstall(&self) -> Result<(), std::io::Error> {
+        let mut command = std::process::Command::new("systemctl");
+        command.arg("--user");
+        command.arg("disable");
+        command.arg("--now");
+        command.arg("potd-wallpaper.service");
+        crate::sandbox::normalize_command(&mut command);
+        command.output()?;
+
+        let service_path = get_home_relative_path(".local/lib/systemd/user/potd-wallpaper.service");
+        let _ = std::fs::remove_file(&service_path);
+
+        let install_path = get_home_relative_path(".local/bin/potd-wallpaper");
+        let _ = std::fs::remove_file(&install_path);
+
+        let _ = std::fs::remove_dir_all(crate::path::get_state_dir());
+        Ok(())
+    }
+
+    fn status(&self) -> Result<crate::ServiceStatus, std::io::Error> {
+        let mut command = std::process::Command::new("systemctl");
+        command.arg("--user");
+        command.arg("is-active");
+        command.arg("potd-wallpaper.service");
+        crate::sandbox::normalize_command(&mut command);
+        let output = command.output()?;
+        let loaded = output.status.success();
+
+        let last_changed = std::fs::metadata(crate::path::get_last_changed_path()).and_then(|m| m.modified()).ok();
+
+        Ok(crate::ServiceStatus { loaded, last_changed })
+    }
 }
 
-pub struct GnuLinuxProvider;
+/// Desktop environments and compositors that `GnuLinuxProvider` knows how to
+/// drive directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DesktopEnvironment {
+    Gnome,
+    Cinnamon,
+    Mate,
+    Xfce,
+    Kde,
+    /// A wlroots-based Wayland compositor (Sway, Hyprland, ...) with no
+    /// desktop-settings daemon of its own.
+    Wlroots,
+}
+
+fn is_wlroots_compositor() -> bool {
+    if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        return false;
+    }
+    let xdg_current_desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default().to_lowercase();
+    ["sway", "hyprland", "wlroots"].iter().any(|name| xdg_current_desktop.contains(name))
+}
+
+fn detect_desktop_environment() -> Option<DesktopEnvironment> {
+    if is_wlroots_compositor() {
+        return Some(DesktopEnvironment::Wlroots);
+    }
+
+    let xdg_current_desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default().to_lowercase();
+    let desktop_session = std::env::var("DESKTOP_SESSION").unwrap_or_default().to_lowercase();
+    let combined = format!("{} {}", xdg_current_desktop, desktop_session);
+
+    if combined.contains("gnome") {
+        Some(DesktopEnvironment::Gnome)
+    } else if combined.contains("cinnamon") {
+        Some(DesktopEnvironment::Cinnamon)
+    } else if combined.contains("mate") {
+        Some(DesktopEnvironment::Mate)
+    } else if combined.contains("xfce") {
+        Some(DesktopEnvironment::Xfce)
+    } else if combined.contains("kde") || combined.contains("plasma") {
+        Some(DesktopEnvironment::Kde)
+    } else {
+        None
+    }
+}
+
+async fn create_wallpaper_directory() -> Result<PathBuf, std::io::Error> {
+    let path = crate::path::get_state_dir();
+    tokio::fs::create_dir_all(&path).await?;
+    Ok(path)
+}
+
+pub struct GnuLinuxProvider {
+    http_client: potd::http_client::HttpClient,
+    wallpaper_counter: AtomicUsize,
+    wallpaper_directory: PathBuf,
+    swaybg_child: Mutex<Option<std::process::Child>>,
+}
+
+impl GnuLinuxProvider {
+    pub fn new(state: &crate::State) -> Result<Self, std::io::Error> {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let wallpaper_directory = create_wallpaper_directory().await?;
+            Ok(Self {
+                http_client: state.http_client(),
+                wallpaper_counter: AtomicUsize::new(0),
+                wallpaper_directory,
+                swaybg_child: Mutex::new(None),
+            })
+        })
+    }
+
+    fn next_wallpaper_path(&self) -> PathBuf {
+        let counter = self.wallpaper_counter.fetch_xor(1, std::sync::atomic::Ordering::Relaxed);
+        self.wallpaper_directory.join(format!("wallpaper-{}.jpg", counter))
+    }
+
+    fn prev_wallpaper_path(&self) -> PathBuf {
+        let counter = self.wallpaper_counter.load(std::sync::atomic::Ordering::Relaxed);
+        self.wallpaper_directory.join(format!("wallpaper-{}.jpg", counter))
+    }
+
+    /// Downloads `url` into the local double-buffered cache and returns the
+    /// path it was written to. The previously cached file is left in place
+    /// until the caller has successfully applied the new one.
+    async fn download_wallpaper(&self, url: &str) -> Result<PathBuf, std::io::Error> {
+        let mut path = self.next_wallpaper_path();
+        if path.exists() {
+            path = self.next_wallpaper_path();
+        }
+        let bytes = self.http_client.fetch_bytes(url, true).await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+        let mut file = tokio::fs::File::create(&path).await?;
+        file.write_all(&bytes).await?;
+        Ok(path)
+    }
+
+    fn apply_gnome(&self, file_uri: &str) -> Result<(), std::io::Error> {
+        for key in ["picture-uri", "picture-uri-dark"] {
+            let mut command = std::process::Command::new("gsettings");
+            command.arg("set");
+            command.arg("org.gnome.desktop.background");
+            command.arg(key);
+            command.arg(file_uri);
+            crate::sandbox::normalize_command(&mut command);
+            command.output()?;
+        }
+        Ok(())
+    }
+
+    fn apply_cinnamon(&self, file_uri: &str) -> Result<(), std::io::Error> {
+        let mut command = std::process::Command::new("gsettings");
+        command.arg("set");
+        command.arg("org.cinnamon.desktop.background");
+        command.arg("picture-uri");
+        command.arg(file_uri);
+        crate::sandbox::normalize_command(&mut command);
+        command.output()?;
+        Ok(())
+    }
+
+    fn apply_mate(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        let mut command = std::process::Command::new("gsettings");
+        command.arg("set");
+        command.arg("org.mate.background");
+        command.arg("picture-filename");
+        command.arg(path);
+        crate::sandbox::normalize_command(&mut command);
+        command.output()?;
+        Ok(())
+    }
+
+    fn apply_xfce(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        let mut command = std::process::Command::new("xfconf-query");
+        command.arg("-c");
+        command.arg("xfce4-desktop");
+        command.arg("-p");
+        command.arg("/backdrop/screen0/monitor0/workspace0/last-image");
+        command.arg("-s");
+        command.arg(path);
+        crate::sandbox::normalize_command(&mut command);
+        command.output()?;
+        Ok(())
+    }
+
+    fn apply_kde(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        let mut command = std::process::Command::new("plasma-apply-wallpaperimage");
+        command.arg(path);
+        crate::sandbox::normalize_command(&mut command);
+        command.output()?;
+        Ok(())
+    }
+
+    /// Prefers `swww img`, which animates the transition and needs no
+    /// process management of its own. Falls back to a long-lived `swaybg`
+    /// process, killing any previously spawned instance either way so a
+    /// `swaybg` fallback never outlives `swww` taking back over.
+    fn apply_wlroots(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        let mut swww_command = std::process::Command::new("swww");
+        swww_command.arg("img");
+        swww_command.arg(path);
+        crate::sandbox::normalize_command(&mut swww_command);
+        let swww_succeeded = swww_command
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+
+        let mut child_slot = self.swaybg_child.lock();
+        if let Some(mut child) = child_slot.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+
+        if swww_succeeded {
+            return Ok(());
+        }
+
+        let mut swaybg_command = std::process::Command::new("swaybg");
+        swaybg_command.arg("-i");
+        swaybg_command.arg(path);
+        crate::sandbox::normalize_command(&mut swaybg_command);
+        let child = swaybg_command.spawn()?;
+        *child_slot = Some(child);
+        Ok(())
+    }
+}
 
 impl PlatformProvider for GnuLinuxProvider {
     fn find_screen_resolution(&self) -> Result<(u32, u32), std::io::Error> {
@@ -55,29 +280,27 @@ impl PlatformProvider for GnuLinuxProvider {
     }
 
     fn set_desktop_wallpaper_url(&self, url: &str) -> Result<(), std::io::Error> {
-        let xdg_current_desktop = std::env::var("XDG_CURRENT_DESKTOP");
-        if let Ok(xdg_current_desktop) = xdg_current_desktop {
-            let xdg_current_desktop = xdg_current_desktop.to_lowercase();
-            if xdg_current_desktop.contains("gnome") {
-                let mut command = std::process::Command::new("gsettings");
-                command.arg("set");
-                command.arg("org.gnome.desktop.background");
-                command.arg("picture-uri");
-                command.arg(url);
-                command.output()?;
-            } else if xdg_current_desktop.contains("cinammon") {
-                let mut command = std::process::Command::new("gsettings");
-                command.arg("set");
-                command.arg("org.cinnamon.desktop.background");
-                command.arg("picture-uri");
-                command.arg(url);
-                command.output()?;
-            } else {
-                return Err(std::io::Error::new(std::io::ErrorKind::Other, "Unsupported desktop environment"));
-            }
-        } else {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "XDG_CURRENT_DESKTOP is not set"));
+        let Some(desktop_environment) = detect_desktop_environment() else {
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, "Unsupported desktop environment"));
+        };
+
+        let path = tokio::runtime::Runtime::new().unwrap().block_on(self.download_wallpaper(url))?;
+        let file_uri = format!("file://{}", path.to_string_lossy());
+
+        let result = match desktop_environment {
+            DesktopEnvironment::Gnome => self.apply_gnome(&file_uri),
+            DesktopEnvironment::Cinnamon => self.apply_cinnamon(&file_uri),
+            DesktopEnvironment::Mate => self.apply_mate(&path),
+            DesktopEnvironment::Xfce => self.apply_xfce(&path),
+            DesktopEnvironment::Kde => self.apply_kde(&path),
+            DesktopEnvironment::Wlroots => self.apply_wlroots(&path),
+        };
+
+        if result.is_ok() {
+            let prev_path = self.prev_wallpaper_path();
+            let _ = std::fs::remove_file(prev_path);
         }
-        Ok(())
+
+        result
     }
 }
@@ -45,7 +45,7 @@ pub async fn set_desktop_wallpaper(path: &str) -> Result<(), std::io::Error> {
 }
 
 async fn create_wallpaper_directory() -> Result<PathBuf, std::io::Error> {
-    let path = get_home_relative_path("Library/potd-wallpaper");
+    let path = crate::path::get_state_dir();
     tokio::fs::create_dir_all(&path).await?;
     Ok(path)
 }
@@ -92,6 +92,30 @@ impl crate::PlatformInstaller for MacosInstaller {
             .output()?;
         Ok(())
     }
+
+    fn uninstall(&self) -> Result<(), std::io::Error> {
+        let agent_file = get_home_relative_path("Library/LaunchAgents/org.menhera.potd-wallpaper.plist");
+        std::process::Command::new("launchctl")
+            .arg("unload")
+            .arg(&agent_file)
+            .output()?;
+        let _ = std::fs::remove_file(&agent_file);
+
+        let wallpaper_dir = get_home_relative_path("Library/potd-wallpaper");
+        let _ = std::fs::remove_file(wallpaper_dir.join("potd-wallpaper"));
+        let _ = std::fs::remove_dir_all(&wallpaper_dir);
+        Ok(())
+    }
+
+    fn status(&self) -> Result<crate::ServiceStatus, std::io::Error> {
+        let output = std::process::Command::new("launchctl")
+            .arg("list")
+            .arg("org.menhera.potd-wallpaper")
+            .output()?;
+        let loaded = output.status.success();
+        let last_changed = std::fs::metadata(crate::path::get_last_changed_path()).and_then(|m| m.modified()).ok();
+        Ok(crate::ServiceStatus { loaded, last_changed })
+    }
 }
 
 pub struct MacosProvider {
@@ -1,5 +1,10 @@
 
+mod control;
 mod path;
+mod sandbox;
+mod schedule;
+
+use schedule::ScheduleMode;
 
 #[cfg(target_os = "macos")]
 mod macos;
@@ -29,15 +34,52 @@ enum Command {
     Run {
         #[arg(short, long, default_value = "300")]
         change_interval: u64,
+
+        /// How to pick the wallpaper to display on each tick
+        #[arg(long, value_enum, default_value_t = ScheduleMode::Random)]
+        schedule: ScheduleMode,
+
+        /// Latitude, for anchoring the time-of-day schedule to sunrise/sunset
+        #[arg(long, requires = "longitude")]
+        latitude: Option<f64>,
+
+        /// Longitude, for anchoring the time-of-day schedule to sunrise/sunset
+        #[arg(long, requires = "latitude")]
+        longitude: Option<f64>,
     },
 
     /// Install the wallpaper changer service for the current user
     #[command()]
     Install,
+
+    /// Uninstall the wallpaper changer service for the current user
+    #[command()]
+    Uninstall,
+
+    /// Report whether the service is running and when the wallpaper last changed
+    #[command()]
+    Status,
+
+    /// Skip to the next wallpaper in the running service's rotation
+    #[command()]
+    Next,
+
+    /// Skip to the previous wallpaper in the running service's rotation
+    #[command()]
+    Prev,
 }
 
 pub trait PlatformInstaller {
     fn install(&self) -> Result<(), std::io::Error>;
+    fn uninstall(&self) -> Result<(), std::io::Error>;
+    fn status(&self) -> Result<ServiceStatus, std::io::Error>;
+}
+
+/// Snapshot of the installed service's state, as reported by `Command::Status`.
+#[derive(Debug, Clone)]
+pub struct ServiceStatus {
+    pub loaded: bool,
+    pub last_changed: Option<std::time::SystemTime>,
 }
 
 pub trait PlatformProvider {
@@ -52,6 +94,13 @@ pub struct Config {
 
     /// Target screen resolution
     pub screen_resolution: (u32, u32),
+
+    /// How to pick the wallpaper to display on each tick
+    pub schedule: ScheduleMode,
+
+    /// Latitude/longitude to anchor the time-of-day schedule to sunrise/sunset
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
 }
 
 impl Default for Config {
@@ -59,6 +108,9 @@ impl Default for Config {
         Self {
             wallpaper_interval: 60 * 10,
             screen_resolution: (1920, 1080),
+            schedule: ScheduleMode::Random,
+            latitude: None,
+            longitude: None,
         }
     }
 }
@@ -106,7 +158,7 @@ fn build_provider(state: &State) -> Box<dyn PlatformProvider> {
     #[cfg(not(target_os = "macos"))]
     {
         #[cfg(target_os = "linux")]
-        return Box::new(gnu_linux::GnuLinuxProvider);
+        return Box::new(gnu_linux::GnuLinuxProvider::new(&state).unwrap());
 
         #[cfg(not(target_os = "linux"))]
         panic!("unsupported platform");
@@ -134,25 +186,70 @@ fn install() {
     }
 }
 
+fn uninstall() {
+    let installer = build_installer();
+    if let Err(e) = installer.uninstall() {
+        panic!("failed to uninstall: {}", e);
+    }
+}
+
+fn status() {
+    let installer = build_installer();
+    match installer.status() {
+        Ok(status) => {
+            println!("loaded: {}", status.loaded);
+            match status.last_changed.and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok()) {
+                Some(duration) => println!("last changed: {} (unix epoch seconds)", duration.as_secs()),
+                None => println!("last changed: never"),
+            }
+        }
+        Err(e) => {
+            log::error!("failed to query status: {}", e);
+        }
+    }
+}
+
+fn next() {
+    if let Err(e) = control::send_request(control::ControlRequest::Next) {
+        log::error!("failed to send next request: {}", e);
+    }
+}
+
+fn prev() {
+    if let Err(e) = control::send_request(control::ControlRequest::Prev) {
+        log::error!("failed to send prev request: {}", e);
+    }
+}
+
 fn main() {
     env_logger::init();
     let args = Args::parse();
     let subcmd = args.subcmd;
 
-    let change_interval = match subcmd {
-        Command::Run { change_interval } => {
-            change_interval
+    let (change_interval, schedule, latitude, longitude) = match subcmd {
+        Command::Run { change_interval, schedule, latitude, longitude } => {
+            (change_interval, schedule, latitude, longitude)
         }
         Command::Install => {
             install();
             return;
         }
-
-        #[allow(unreachable_patterns)]
-        _ => {
-            log::error!("unsupported subcommand");
+        Command::Uninstall => {
+            uninstall();
             return;
-        },
+        }
+        Command::Status => {
+            status();
+            return;
+        }
+        Command::Next => {
+            next();
+            return;
+        }
+        Command::Prev => {
+            prev();
+            return;
+        }
     };
 
     let screen_resolution = {
@@ -167,6 +264,9 @@ fn main() {
     let mut config = Config::default();
     config.screen_resolution = screen_resolution;
     config.wallpaper_interval = change_interval;
+    config.schedule = schedule;
+    config.latitude = latitude;
+    config.longitude = longitude;
     let state = State::new(config);
     let provider = build_provider(&state);
 
@@ -189,7 +289,14 @@ fn main() {
         }
     });
 
+    let (control_tx, control_rx) = std::sync::mpsc::channel();
+    if let Err(e) = control::spawn_listener(control_tx) {
+        log::error!("failed to start control socket: {}", e);
+    }
+
     let mut rng = rand::thread_rng();
+    let mut last_index: Option<usize> = None;
+    let mut pending_control: Option<control::ControlRequest> = None;
     loop {
         let urls = loop {
             let urls = state.picture_urls.read().clone();
@@ -199,11 +306,28 @@ fn main() {
             }
             break urls;
         };
-        let index: u64 = rng.gen_range(0..urls.len() as u64);
-        let url = &urls[index as usize];
-        if let Err(e) = provider.set_desktop_wallpaper_url(url) {
-            log::error!("failed to set wallpaper: {}", e);
+
+        let index = match pending_control.take() {
+            Some(control::ControlRequest::Next) => (last_index.unwrap_or(0) + 1) % urls.len(),
+            Some(control::ControlRequest::Prev) => (last_index.unwrap_or(0) + urls.len() - 1) % urls.len(),
+            None => match state.config.schedule {
+                ScheduleMode::Random => rng.gen_range(0..urls.len() as u64) as usize,
+                ScheduleMode::TimeOfDay => schedule::time_of_day_index(urls.len(), state.config.latitude, state.config.longitude),
+            },
+        };
+
+        if state.config.schedule == ScheduleMode::Random || last_index != Some(index) {
+            let url = &urls[index];
+            if let Err(e) = provider.set_desktop_wallpaper_url(url) {
+                log::error!("failed to set wallpaper: {}", e);
+            } else {
+                if let Err(e) = path::touch_last_changed() {
+                    log::error!("failed to record wallpaper change: {}", e);
+                }
+                last_index = Some(index);
+            }
         }
-        std::thread::sleep(std::time::Duration::from_secs(state.config.wallpaper_interval));
+
+        pending_control = control_rx.recv_timeout(std::time::Duration::from_secs(state.config.wallpaper_interval)).ok();
     }
 }
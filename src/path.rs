@@ -7,3 +7,34 @@ pub fn get_home_relative_path(path: impl AsRef<Path>) -> PathBuf {
     home.join(path)
 }
 
+/// Directory holding the running service's cache and state: downloaded
+/// wallpapers, and the `last-changed` marker file.
+pub fn get_state_dir() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    return get_home_relative_path("Library/potd-wallpaper");
+
+    #[cfg(target_os = "linux")]
+    return get_home_relative_path(".local/share/potd-wallpaper");
+}
+
+/// Marker file whose mtime records when the wallpaper was last changed.
+pub fn get_last_changed_path() -> PathBuf {
+    get_state_dir().join("last-changed")
+}
+
+/// Records that the wallpaper was just changed, for `Status` to report.
+pub fn touch_last_changed() -> Result<(), std::io::Error> {
+    let path = get_last_changed_path();
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(path, [])
+}
+
+/// Per-user control socket that a running `run` loop listens on, and that
+/// one-shot `next`/`prev` invocations connect to.
+pub fn get_socket_path() -> PathBuf {
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        return PathBuf::from(runtime_dir).join("potd-wallpaper.sock");
+    }
+    get_state_dir().join("potd-wallpaper.sock")
+}
+
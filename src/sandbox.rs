@@ -0,0 +1,139 @@
+
+use std::collections::HashSet;
+
+/// Sandboxing formats whose inherited environment points into the sandbox
+/// rather than the host, confusing host tools spawned via
+/// `std::process::Command`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SandboxKind {
+    Flatpak,
+    Snap,
+    AppImage,
+}
+
+fn detect() -> Option<SandboxKind> {
+    if std::path::Path::new("/.flatpak-info").exists() {
+        return Some(SandboxKind::Flatpak);
+    }
+    if std::env::var_os("SNAP").is_some() {
+        return Some(SandboxKind::Snap);
+    }
+    if std::env::var_os("APPIMAGE").is_some() {
+        return Some(SandboxKind::AppImage);
+    }
+    None
+}
+
+fn sandbox_prefix(kind: SandboxKind) -> Option<String> {
+    match kind {
+        SandboxKind::Flatpak => Some("/app".to_string()),
+        SandboxKind::Snap => std::env::var("SNAP").ok(),
+        SandboxKind::AppImage => std::env::var("APPDIR").ok(),
+    }
+}
+
+const PATHLIST_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "GST_PLUGIN_SYSTEM_PATH",
+    "XDG_DATA_DIRS",
+    "XDG_CONFIG_DIRS",
+];
+
+/// Splits a colon-separated path list, drops entries living under `prefix`,
+/// and de-duplicates while keeping each directory's first (host-preferred)
+/// occurrence. Returns `None` if nothing is left, so callers can drop the
+/// variable entirely instead of setting it to `""`.
+pub fn normalize_pathlist(value: &str, prefix: &str) -> Option<String> {
+    let mut seen = HashSet::new();
+    let mut kept = Vec::new();
+    for entry in value.split(':') {
+        if entry.is_empty() {
+            continue;
+        }
+        if !prefix.is_empty() && (entry == prefix || entry.starts_with(&format!("{prefix}/"))) {
+            continue;
+        }
+        if seen.insert(entry) {
+            kept.push(entry);
+        }
+    }
+    if kept.is_empty() {
+        None
+    } else {
+        Some(kept.join(":"))
+    }
+}
+
+/// Rewrites `command`'s environment so host tools (`gsettings`, `systemctl`,
+/// `swww`, ...) spawned from inside a Flatpak/Snap/AppImage sandbox see a
+/// clean, host-only view of the path-like variables, instead of inheriting
+/// paths that point back into the sandbox.
+pub fn normalize_command(command: &mut std::process::Command) {
+    let Some(kind) = detect() else {
+        return;
+    };
+    let Some(prefix) = sandbox_prefix(kind) else {
+        return;
+    };
+
+    for var in PATHLIST_VARS {
+        let Ok(value) = std::env::var(var) else {
+            continue;
+        };
+        match normalize_pathlist(&value, &prefix) {
+            Some(normalized) => {
+                command.env(var, normalized);
+            }
+            None => {
+                command.env_remove(var);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_entries_under_the_sandbox_prefix() {
+        assert_eq!(
+            normalize_pathlist("/app/bin:/usr/bin:/app/lib", "/app"),
+            Some("/usr/bin".to_string())
+        );
+    }
+
+    #[test]
+    fn prefix_match_requires_a_path_boundary() {
+        // "/application" only shares a string prefix with "/app", not a path
+        // component, so it must survive normalization.
+        assert_eq!(
+            normalize_pathlist("/app:/application/bin", "/app"),
+            Some("/application/bin".to_string())
+        );
+    }
+
+    #[test]
+    fn drops_the_prefix_directory_itself() {
+        assert_eq!(normalize_pathlist("/app:/usr/bin", "/app"), Some("/usr/bin".to_string()));
+    }
+
+    #[test]
+    fn returns_none_when_everything_is_dropped() {
+        assert_eq!(normalize_pathlist("/app/bin:/app/lib", "/app"), None);
+    }
+
+    #[test]
+    fn deduplicates_keeping_the_first_occurrence() {
+        assert_eq!(
+            normalize_pathlist("/usr/bin:/usr/local/bin:/usr/bin", "/app"),
+            Some("/usr/bin:/usr/local/bin".to_string())
+        );
+    }
+
+    #[test]
+    fn ignores_empty_entries() {
+        assert_eq!(normalize_pathlist("/usr/bin::/usr/local/bin", "/app"), Some("/usr/bin:/usr/local/bin".to_string()));
+    }
+}
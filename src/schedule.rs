@@ -0,0 +1,160 @@
+
+use clap::ValueEnum;
+
+/// How the running wallpaper changer picks the next picture from
+/// `picture_urls` on each tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ScheduleMode {
+    /// Pick a uniformly random picture on every tick (the original behavior).
+    Random,
+    /// Pick a picture based on the time of day.
+    TimeOfDay,
+}
+
+impl Default for ScheduleMode {
+    fn default() -> Self {
+        ScheduleMode::Random
+    }
+}
+
+/// Picks the index into a `len`-long `picture_urls` list for the current
+/// time of day, dyn-wall-rs style: the day is divided into `len` equal
+/// segments of `1440 / len` minutes, and the segment the current time falls
+/// into selects the picture.
+///
+/// When `latitude`/`longitude` are given, segments are anchored to sunrise
+/// and sunset instead, so darker pictures land at night.
+pub fn time_of_day_index(len: usize, latitude: Option<f64>, longitude: Option<f64>) -> usize {
+    let len = len.max(1);
+
+    if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+        if let Some(index) = solar_anchored_index(len, latitude, longitude) {
+            return index;
+        }
+    }
+
+    let minutes_since_midnight = local_minutes_since_midnight();
+    uniform_segment_index(len, minutes_since_midnight)
+}
+
+fn uniform_segment_index(len: usize, minutes_since_midnight: u32) -> usize {
+    let segment_minutes = ((24 * 60) / len as u32).max(1);
+    ((minutes_since_midnight / segment_minutes) as usize).min(len - 1)
+}
+
+fn local_minutes_since_midnight() -> u32 {
+    let now = chrono::Local::now();
+    use chrono::Timelike;
+    now.hour() * 60 + now.minute()
+}
+
+/// Splits the day into a "night" half anchored to sunset..sunrise and a
+/// "day" half anchored to sunrise..sunset, then subdivides each half evenly
+/// across the pictures assigned to it. Returns `None` when sunrise/sunset
+/// cannot be computed (e.g. polar day/night) so the caller can fall back to
+/// the uniform schedule.
+fn solar_anchored_index(len: usize, latitude: f64, longitude: f64) -> Option<usize> {
+    if len == 1 {
+        // A single picture has no day/night split to anchor.
+        return Some(0);
+    }
+
+    let now = chrono::Utc::now();
+    let (sunrise_minutes, sunset_minutes) = solar_noon_minutes(now, latitude, longitude)?;
+    if sunrise_minutes >= sunset_minutes {
+        return None;
+    }
+
+    let minutes_since_midnight = local_minutes_since_midnight();
+    // Safe for len >= 2: day_len is in [1, len-1], so night_len = len - day_len >= 1.
+    let day_len = (len / 2).max(1);
+    let night_len = len - day_len;
+
+    if minutes_since_midnight >= sunrise_minutes && minutes_since_midnight < sunset_minutes {
+        let offset = minutes_since_midnight - sunrise_minutes;
+        let segment_minutes = ((sunset_minutes - sunrise_minutes) / day_len as u32).max(1);
+        Some((offset / segment_minutes).min(day_len as u32 - 1) as usize)
+    } else {
+        let night_minutes = 24 * 60 - (sunset_minutes - sunrise_minutes);
+        let offset = if minutes_since_midnight >= sunset_minutes {
+            minutes_since_midnight - sunset_minutes
+        } else {
+            minutes_since_midnight + 24 * 60 - sunset_minutes
+        };
+        let segment_minutes = (night_minutes / night_len as u32).max(1);
+        Some(day_len + (offset / segment_minutes).min(night_len as u32 - 1) as usize)
+    }
+}
+
+/// Approximate sunrise/sunset time (in local minutes since midnight) using
+/// the standard NOAA solar position formulas.
+fn solar_noon_minutes(now: chrono::DateTime<chrono::Utc>, latitude: f64, longitude: f64) -> Option<(u32, u32)> {
+    use chrono::Datelike;
+
+    let day_of_year = now.ordinal() as f64;
+    let fractional_year = 2.0 * std::f64::consts::PI / 365.0 * (day_of_year - 1.0);
+
+    let declination = 0.006918
+        - 0.399912 * fractional_year.cos()
+        + 0.070257 * fractional_year.sin()
+        - 0.006758 * (2.0 * fractional_year).cos()
+        + 0.000907 * (2.0 * fractional_year).sin()
+        - 0.002697 * (3.0 * fractional_year).cos()
+        + 0.00148 * (3.0 * fractional_year).sin();
+
+    let latitude_rad = latitude.to_radians();
+    let cos_hour_angle = -latitude_rad.tan() * declination.tan();
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        // Polar day or polar night: there is no meaningful sunrise/sunset.
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    let solar_noon_utc_minutes = 720.0 - 4.0 * longitude;
+    let half_day_minutes = 4.0 * hour_angle;
+
+    let local_offset_minutes = chrono::Local::now().offset().local_minus_utc() as f64 / 60.0;
+    let sunrise = (solar_noon_utc_minutes - half_day_minutes + local_offset_minutes).rem_euclid(24.0 * 60.0);
+    let sunset = (solar_noon_utc_minutes + half_day_minutes + local_offset_minutes).rem_euclid(24.0 * 60.0);
+
+    Some((sunrise as u32, sunset as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_segment_index_single_picture_never_divides_by_zero() {
+        assert_eq!(uniform_segment_index(1, 0), 0);
+        assert_eq!(uniform_segment_index(1, 719), 0);
+        assert_eq!(uniform_segment_index(1, 1439), 0);
+    }
+
+    #[test]
+    fn uniform_segment_index_even_segment_count() {
+        // 4 pictures -> 360-minute segments.
+        assert_eq!(uniform_segment_index(4, 0), 0);
+        assert_eq!(uniform_segment_index(4, 359), 0);
+        assert_eq!(uniform_segment_index(4, 360), 1);
+        assert_eq!(uniform_segment_index(4, 1079), 2);
+        assert_eq!(uniform_segment_index(4, 1439), 3);
+    }
+
+    #[test]
+    fn uniform_segment_index_odd_segment_count() {
+        // 3 pictures -> 480-minute segments.
+        assert_eq!(uniform_segment_index(3, 0), 0);
+        assert_eq!(uniform_segment_index(3, 479), 0);
+        assert_eq!(uniform_segment_index(3, 480), 1);
+        assert_eq!(uniform_segment_index(3, 959), 1);
+        assert_eq!(uniform_segment_index(3, 960), 2);
+        // The last minute of the day falls in the last segment.
+        assert_eq!(uniform_segment_index(3, 1439), 2);
+    }
+
+    #[test]
+    fn solar_anchored_index_single_picture_has_no_split_to_anchor() {
+        assert_eq!(solar_anchored_index(1, 35.6, 139.7), Some(0));
+    }
+}